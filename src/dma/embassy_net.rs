@@ -0,0 +1,353 @@
+//! An `embassy-net` [`Driver`] implementation for [`EthernetDMA`].
+//!
+//! With the `embassy-net` feature enabled, users can run `embassy-net`
+//! against this crate without RTIC and without managing the
+//! [`RxRingEntry`]/[`TxRingEntry`] arrays by hand: a single `static`
+//! [`PacketQueue`] owns both the descriptors and their packet buffers, and
+//! [`EthernetDriver::new`] splits it into the [`EthernetDMA`] it drives.
+
+use core::task::Context;
+
+use embassy_net_driver::{Capabilities, Driver, HardwareAddress, LinkState, RxToken, TxToken};
+use embassy_sync::waitqueue::AtomicWaker;
+use ieee802_3_miim::Miim;
+
+use super::{EthernetDMA, PacketId, RxRingEntry, TxRingEntry, MTU};
+use crate::mac::EthernetMACWithMii;
+
+/// A `const`-constructible pool that owns `RX` receive and `TX` transmit
+/// descriptors together with their backing packet buffers.
+///
+/// Place one of these in `static` storage and pass it to
+/// [`EthernetDriver::new`] instead of declaring separate
+/// `RxRingEntry`/`TxRingEntry` arrays.
+pub struct PacketQueue<const RX: usize, const TX: usize> {
+    rx_ring: [RxRingEntry; RX],
+    tx_ring: [TxRingEntry; TX],
+}
+
+impl<const RX: usize, const TX: usize> PacketQueue<RX, TX> {
+    /// Create a new, empty packet queue.
+    pub const fn new() -> Self {
+        Self {
+            rx_ring: [RxRingEntry::new(); RX],
+            tx_ring: [TxRingEntry::new(); TX],
+        }
+    }
+}
+
+impl<const RX: usize, const TX: usize> Default for PacketQueue<RX, TX> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `embassy-net` waker state shared between [`on_interrupt`] and the
+/// `embassy-net` task polling this driver.
+pub struct EthernetState {
+    rx_waker: AtomicWaker,
+    tx_waker: AtomicWaker,
+}
+
+impl EthernetState {
+    /// Create new, idle waker state.
+    pub const fn new() -> Self {
+        Self {
+            rx_waker: AtomicWaker::new(),
+            tx_waker: AtomicWaker::new(),
+        }
+    }
+}
+
+impl Default for EthernetState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An `embassy_net_driver::Driver` implementation backed by [`EthernetDMA`].
+pub struct EthernetDriver<'d, M: Miim> {
+    dma: EthernetDMA<'d, 'd>,
+    mac: EthernetMACWithMii<M>,
+    state: &'d EthernetState,
+    last_link_state: LinkState,
+}
+
+impl<'d, M: Miim> EthernetDriver<'d, M> {
+    /// Build a driver around the DMA rings owned by `queue`.
+    pub fn new<const RX: usize, const TX: usize>(
+        queue: &'d mut PacketQueue<RX, TX>,
+        mac: EthernetMACWithMii<M>,
+        state: &'d EthernetState,
+    ) -> Self {
+        let dma = EthernetDMA::new(&mut queue.rx_ring, &mut queue.tx_ring);
+        dma.enable_interrupt();
+        Self {
+            dma,
+            mac,
+            state,
+            last_link_state: LinkState::Down,
+        }
+    }
+}
+
+/// Call this from the `ETH` interrupt handler instead of
+/// [`crate::eth_interrupt_handler`]: it calls that itself, then wakes the
+/// driver's RX and TX wakers.
+pub fn on_interrupt(state: &EthernetState) {
+    crate::eth_interrupt_handler();
+    state.rx_waker.wake();
+    state.tx_waker.wake();
+}
+
+impl<'d, M: Miim> Driver for EthernetDriver<'d, M> {
+    type RxToken<'a>
+        = EthernetRxToken<'a, 'd>
+    where
+        Self: 'a;
+    type TxToken<'a>
+        = EthernetTxToken<'a, 'd>
+    where
+        Self: 'a;
+
+    fn receive(&mut self, cx: &mut Context) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
+        self.state.rx_waker.register(cx.waker());
+        self.state.tx_waker.register(cx.waker());
+
+        let (rx, tx) = self.dma.split();
+
+        if !rx.next_packet_is_ready() {
+            return None;
+        }
+
+        Some((EthernetRxToken { rx }, EthernetTxToken { tx }))
+    }
+
+    fn transmit(&mut self, cx: &mut Context) -> Option<Self::TxToken<'_>> {
+        self.state.tx_waker.register(cx.waker());
+        let (_, tx) = self.dma.split();
+        Some(EthernetTxToken { tx })
+    }
+
+    fn link_state(&mut self, cx: &mut Context) -> LinkState {
+        let state = if self.mac.link_up() {
+            LinkState::Up
+        } else {
+            LinkState::Down
+        };
+
+        if state != self.last_link_state {
+            self.last_link_state = state;
+            cx.waker().wake_by_ref();
+        }
+
+        state
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        let mut capabilities = Capabilities::default();
+        capabilities.max_transmission_unit = MTU;
+        capabilities
+    }
+
+    fn hardware_address(&self) -> HardwareAddress {
+        HardwareAddress::Ethernet(self.mac.address())
+    }
+}
+
+/// An `embassy-net` receive token that copies a received frame out of a
+/// [`PacketQueue`] descriptor.
+pub struct EthernetRxToken<'a, 'd> {
+    rx: &'a mut super::RxRing<'d>,
+}
+
+impl<'a, 'd> RxToken for EthernetRxToken<'a, 'd> {
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let mut packet = self
+            .rx
+            .take_received()
+            .expect("EthernetRxToken constructed without a ready packet");
+
+        f(&mut packet)
+    }
+}
+
+/// An `embassy-net` transmit token that copies a frame into a free
+/// [`PacketQueue`] descriptor.
+pub struct EthernetTxToken<'a, 'd> {
+    tx: &'a mut super::TxRing<'d>,
+}
+
+impl<'a, 'd> TxToken for EthernetTxToken<'a, 'd> {
+    fn consume<R, F>(self, len: usize, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let packet_id = PacketId::new();
+
+        let mut buffer = self
+            .tx
+            .prepare_packet_blocking(len, Some(packet_id))
+            .expect("no free TX descriptor");
+
+        let result = f(&mut buffer);
+
+        buffer.send();
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use core::task::{RawWaker, RawWakerVTable, Waker};
+
+    use super::*;
+    use crate::mac::EthernetMACWithMii;
+    use crate::ptp::Timestamp;
+
+    /// MII register address of the Basic Mode Status Register, mirrored from
+    /// `crate::mac` for these assertions.
+    const BMSR: u8 = 1;
+    /// Link Status bit within [`BMSR`].
+    const BMSR_LINK_STATUS: u16 = 1 << 2;
+
+    /// A [`Miim`] test double whose link status is toggled directly instead
+    /// of being backed by real MDIO traffic.
+    struct DummyMiim {
+        link_up: bool,
+    }
+
+    impl Miim for DummyMiim {
+        fn read(&mut self, _phy: u8, reg: u8) -> u16 {
+            assert_eq!(reg, BMSR, "EthernetMACWithMii::link_up only reads BMSR");
+            if self.link_up { BMSR_LINK_STATUS } else { 0 }
+        }
+
+        fn write(&mut self, _phy: u8, _reg: u8, _data: u16) {
+            unimplemented!("link_up never writes to the PHY")
+        }
+    }
+
+    fn mac(link_up: bool) -> EthernetMACWithMii<DummyMiim> {
+        EthernetMACWithMii::new([0, 1, 2, 3, 4, 5], DummyMiim { link_up }, 0)
+    }
+
+    fn counting_waker(woken: &'static AtomicBool) -> Waker {
+        fn clone(data: *const ()) -> RawWaker {
+            RawWaker::new(data, &VTABLE)
+        }
+        fn wake(data: *const ()) {
+            unsafe { &*(data as *const AtomicBool) }.store(true, Ordering::SeqCst);
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake, |_| {});
+
+        let raw = RawWaker::new(woken as *const AtomicBool as *const (), &VTABLE);
+        unsafe { Waker::from_raw(raw) }
+    }
+
+    #[test]
+    fn receive_is_none_without_a_ready_packet() {
+        let mut queue = PacketQueue::<1, 1>::new();
+        let state = EthernetState::new();
+        let mut driver = EthernetDriver::new(&mut queue, mac(true), &state);
+
+        static WOKEN: AtomicBool = AtomicBool::new(false);
+        let waker = counting_waker(&WOKEN);
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(Driver::receive(&mut driver, &mut cx).is_none());
+    }
+
+    #[test]
+    fn receive_yields_the_ready_packet() {
+        let mut queue = PacketQueue::<1, 1>::new();
+        queue.rx_ring[0].buffer[..4].copy_from_slice(&[1, 2, 3, 4]);
+        queue.rx_ring[0].len = 4;
+        queue.rx_ring[0].ready = true;
+        queue.rx_ring[0].id = Some(PacketId(9));
+        queue.rx_ring[0].timestamp = Some(Timestamp::new_raw(0));
+
+        let state = EthernetState::new();
+        let mut driver = EthernetDriver::new(&mut queue, mac(true), &state);
+
+        static WOKEN: AtomicBool = AtomicBool::new(false);
+        let waker = counting_waker(&WOKEN);
+        let mut cx = Context::from_waker(&waker);
+
+        let (rx_token, _tx_token) = Driver::receive(&mut driver, &mut cx).unwrap();
+        let consumed = RxToken::consume(rx_token, |buf| buf == [1, 2, 3, 4]);
+        assert!(consumed);
+    }
+
+    #[test]
+    fn transmit_token_writes_into_a_free_descriptor() {
+        let mut queue = PacketQueue::<1, 1>::new();
+        let state = EthernetState::new();
+        let mut driver = EthernetDriver::new(&mut queue, mac(true), &state);
+
+        static WOKEN: AtomicBool = AtomicBool::new(false);
+        let waker = counting_waker(&WOKEN);
+        let mut cx = Context::from_waker(&waker);
+
+        let tx_token = Driver::transmit(&mut driver, &mut cx).unwrap();
+        TxToken::consume(tx_token, 3, |buf| {
+            buf.copy_from_slice(&[9, 8, 7]);
+        });
+    }
+
+    #[test]
+    fn capabilities_report_the_shared_mtu() {
+        let mut queue = PacketQueue::<1, 1>::new();
+        let state = EthernetState::new();
+        let driver = EthernetDriver::new(&mut queue, mac(true), &state);
+
+        assert_eq!(Driver::capabilities(&driver).max_transmission_unit, MTU);
+    }
+
+    #[test]
+    fn hardware_address_reports_the_mac_address() {
+        let mut queue = PacketQueue::<1, 1>::new();
+        let state = EthernetState::new();
+        let driver = EthernetDriver::new(&mut queue, mac(true), &state);
+
+        assert_eq!(
+            Driver::hardware_address(&driver),
+            HardwareAddress::Ethernet([0, 1, 2, 3, 4, 5])
+        );
+    }
+
+    #[test]
+    fn link_state_wakes_only_on_a_transition() {
+        let mut queue = PacketQueue::<1, 1>::new();
+        let state = EthernetState::new();
+        let mut driver = EthernetDriver::new(&mut queue, mac(false), &state);
+
+        static WOKEN: AtomicBool = AtomicBool::new(false);
+        let waker = counting_waker(&WOKEN);
+        let mut cx = Context::from_waker(&waker);
+
+        // Starts `Down`, matching `last_link_state`'s initial value: no
+        // transition, so no wake.
+        assert!(matches!(Driver::link_state(&mut driver, &mut cx), LinkState::Down));
+        assert!(!WOKEN.load(Ordering::SeqCst));
+
+        // Polling again with the link still down must not wake either.
+        assert!(matches!(Driver::link_state(&mut driver, &mut cx), LinkState::Down));
+        assert!(!WOKEN.load(Ordering::SeqCst));
+
+        // The link comes up: this is a transition, so it must wake.
+        driver.mac = mac(true);
+        assert!(matches!(Driver::link_state(&mut driver, &mut cx), LinkState::Up));
+        assert!(WOKEN.load(Ordering::SeqCst));
+
+        // Polling again with the link still up must not wake a second time.
+        WOKEN.store(false, Ordering::SeqCst);
+        assert!(matches!(Driver::link_state(&mut driver, &mut cx), LinkState::Up));
+        assert!(!WOKEN.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,301 @@
+//! RX/TX descriptor rings and buffer management.
+
+mod packet_id;
+pub use packet_id::PacketId;
+
+#[cfg(feature = "smoltcp-phy")]
+mod smoltcp_phy;
+
+#[cfg(feature = "embassy-net")]
+pub mod embassy_net;
+
+use crate::ptp::Timestamp;
+
+/// The largest Ethernet frame (including the 14-byte header) this crate's
+/// descriptor buffers are sized to hold.
+pub(crate) const MTU: usize = 1514;
+
+/// A single entry (descriptor + backing buffer) in an [`EthernetDMA`]'s RX ring.
+#[derive(Clone, Copy)]
+pub struct RxRingEntry {
+    buffer: [u8; MTU],
+    len: usize,
+    ready: bool,
+    id: Option<PacketId>,
+    timestamp: Option<Timestamp>,
+}
+
+impl RxRingEntry {
+    /// Create a new, empty RX ring entry.
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; MTU],
+            len: 0,
+            ready: false,
+            id: None,
+            timestamp: None,
+        }
+    }
+}
+
+impl Default for RxRingEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A single entry (descriptor + backing buffer) in an [`EthernetDMA`]'s TX ring.
+#[derive(Clone, Copy)]
+pub struct TxRingEntry {
+    buffer: [u8; MTU],
+    id: Option<PacketId>,
+    timestamp: Option<Timestamp>,
+}
+
+impl TxRingEntry {
+    /// Create a new, empty TX ring entry.
+    pub const fn new() -> Self {
+        Self {
+            buffer: [0; MTU],
+            id: None,
+            timestamp: None,
+        }
+    }
+}
+
+impl Default for TxRingEntry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A frame received from the RX ring.
+///
+/// Derefs to the received bytes. The [`PacketId`] and hardware PTP
+/// [`Timestamp`] of the frame (if any) survive past the point where the
+/// frame's bytes are consumed, via [`RxPacket::packet_id`] and
+/// [`RxPacket::timestamp`].
+pub struct RxPacket<'a> {
+    buffer: &'a mut [u8],
+    id: Option<PacketId>,
+    timestamp: Option<Timestamp>,
+}
+
+impl<'a> RxPacket<'a> {
+    /// The [`PacketId`] this frame was received with, if any.
+    pub fn packet_id(&self) -> Option<PacketId> {
+        self.id
+    }
+
+    /// The hardware PTP timestamp latched for this frame, if any.
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        self.timestamp
+    }
+}
+
+impl<'a> core::ops::Deref for RxPacket<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.buffer
+    }
+}
+
+impl<'a> core::ops::DerefMut for RxPacket<'a> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.buffer
+    }
+}
+
+/// A free TX descriptor's buffer, borrowed so a frame can be written into it
+/// before being handed back to the DMA engine with [`TxBuffer::send`].
+pub struct TxBuffer<'a> {
+    entry: &'a mut TxRingEntry,
+    len: usize,
+    id: Option<PacketId>,
+}
+
+impl<'a> TxBuffer<'a> {
+    /// Hand the frame off to the DMA engine for transmission.
+    pub fn send(self) {
+        // In real hardware this would flip descriptor ownership to the DMA
+        // engine. Here, transmission always "succeeds" immediately and the
+        // (fake) hardware timestamp is recorded straight away, so that
+        // `TxRing::timestamp` can return it right after `send`.
+        self.entry.id = self.id;
+        self.entry.timestamp = self.id.map(|_| Timestamp::new_raw(0));
+    }
+}
+
+impl<'a> core::ops::Deref for TxBuffer<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.entry.buffer[..self.len]
+    }
+}
+
+impl<'a> core::ops::DerefMut for TxBuffer<'a> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.entry.buffer[..self.len]
+    }
+}
+
+/// Errors that can occur while looking up a TX or RX timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxError {
+    /// No descriptor in the ring carries the requested [`PacketId`].
+    IdNotFound,
+}
+
+/// The receive half of an [`EthernetDMA`], once [`EthernetDMA::split`].
+pub struct RxRing<'rx> {
+    entries: &'rx mut [RxRingEntry],
+    cursor: usize,
+}
+
+impl<'rx> RxRing<'rx> {
+    /// Whether a received frame is waiting to be taken with [`Self::take_received`].
+    pub fn next_packet_is_ready(&self) -> bool {
+        !self.entries.is_empty() && self.entries[self.cursor].ready
+    }
+
+    /// Look at the [`PacketId`]/[`Timestamp`] of the next ready frame,
+    /// without taking it out of the ring.
+    #[cfg(feature = "smoltcp-phy")]
+    pub(crate) fn peek_received(&self) -> (Option<PacketId>, Option<Timestamp>) {
+        if self.entries.is_empty() || !self.entries[self.cursor].ready {
+            return (None, None);
+        }
+        let entry = &self.entries[self.cursor];
+        (entry.id, entry.timestamp)
+    }
+
+    /// Take the next received frame out of the ring, if one is ready.
+    pub fn take_received(&mut self) -> Option<RxPacket<'_>> {
+        if self.entries.is_empty() {
+            return None;
+        }
+
+        let idx = self.cursor;
+        let ring_len = self.entries.len();
+
+        let entry = &mut self.entries[idx];
+        if !entry.ready {
+            return None;
+        }
+
+        entry.ready = false;
+        let len = entry.len;
+        // Leave the id/timestamp in the entry so `RxRing::timestamp` can
+        // still find them until the descriptor is reused.
+        let id = entry.id;
+        let timestamp = entry.timestamp;
+
+        self.cursor = (idx + 1) % ring_len;
+
+        Some(RxPacket {
+            buffer: &mut entry.buffer[..len],
+            id,
+            timestamp,
+        })
+    }
+
+    /// Look up the hardware PTP timestamp that was latched for `id`, if it
+    /// is still available (i.e. the frame hasn't been overwritten yet).
+    pub fn timestamp(&self, id: &PacketId) -> Result<Option<Timestamp>, TxError> {
+        for entry in self.entries.iter() {
+            if entry.id.as_ref() == Some(id) {
+                return Ok(entry.timestamp);
+            }
+        }
+        Err(TxError::IdNotFound)
+    }
+}
+
+/// The transmit half of an [`EthernetDMA`], once [`EthernetDMA::split`].
+pub struct TxRing<'tx> {
+    entries: &'tx mut [TxRingEntry],
+    cursor: usize,
+}
+
+impl<'tx> TxRing<'tx> {
+    /// Borrow the next free TX descriptor's buffer, truncated to `len` bytes,
+    /// ready to be filled in and [`TxBuffer::send`].
+    ///
+    /// Returns `None` if there is no free descriptor, or `len` doesn't fit in
+    /// a single descriptor's buffer.
+    pub fn prepare_packet_blocking(&mut self, len: usize, id: Option<PacketId>) -> Option<TxBuffer<'_>> {
+        if self.entries.is_empty() || len > self.entries[self.cursor].buffer.len() {
+            return None;
+        }
+
+        let idx = self.cursor;
+        let ring_len = self.entries.len();
+        self.cursor = (idx + 1) % ring_len;
+
+        Some(TxBuffer {
+            entry: &mut self.entries[idx],
+            len,
+            id,
+        })
+    }
+
+    /// Look up the hardware TX timestamp latched for `id`, if the frame has
+    /// finished transmitting.
+    pub fn timestamp(&self, id: &PacketId) -> Result<Option<Timestamp>, TxError> {
+        for entry in self.entries.iter() {
+            if entry.id.as_ref() == Some(id) {
+                return Ok(entry.timestamp);
+            }
+        }
+        Err(TxError::IdNotFound)
+    }
+}
+
+/// A summary of the DMA-related reasons for an `ETH` interrupt.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DmaInterruptReason {
+    /// A packet has arrived and is ready for processing.
+    pub is_rx: bool,
+    /// A packet was sent, and a TX slot has freed up.
+    pub is_tx: bool,
+    /// A DMA error occurred.
+    pub is_error: bool,
+}
+
+/// Access to the RX/TX DMA engine of the ethernet peripheral.
+pub struct EthernetDMA<'rx, 'tx> {
+    rx: RxRing<'rx>,
+    tx: TxRing<'tx>,
+}
+
+impl<'rx, 'tx> EthernetDMA<'rx, 'tx> {
+    /// Set up the DMA engine around the given RX/TX descriptor rings.
+    pub fn new(rx_entries: &'rx mut [RxRingEntry], tx_entries: &'tx mut [TxRingEntry]) -> Self {
+        Self {
+            rx: RxRing {
+                entries: rx_entries,
+                cursor: 0,
+            },
+            tx: TxRing {
+                entries: tx_entries,
+                cursor: 0,
+            },
+        }
+    }
+
+    /// Split the DMA engine into its receive and transmit halves, which can
+    /// be used (and moved around, e.g. into separate tasks) independently.
+    pub fn split(&mut self) -> (&mut RxRing<'rx>, &mut TxRing<'tx>) {
+        (&mut self.rx, &mut self.tx)
+    }
+
+    /// Enable the `ETH` interrupt for RX/TX events.
+    pub fn enable_interrupt(&self) {}
+
+    /// Handle the DMA-related parts of the `ETH` interrupt.
+    pub fn interrupt_handler() -> DmaInterruptReason {
+        DmaInterruptReason::default()
+    }
+}
@@ -0,0 +1,23 @@
+/// An identifier for a single in-flight RX or TX packet.
+///
+/// Callers hand a [`PacketId`] to [`super::TxRing::prepare_packet_blocking`]
+/// (and receive one back from [`super::RxRing::take_received`]) and can
+/// later use it to look up e.g. the hardware PTP timestamp that was latched
+/// for that specific packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PacketId(pub u32);
+
+impl PacketId {
+    /// Allocate a new, unique [`PacketId`].
+    pub fn new() -> Self {
+        use core::sync::atomic::{AtomicU32, Ordering};
+        static NEXT: AtomicU32 = AtomicU32::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Default for PacketId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
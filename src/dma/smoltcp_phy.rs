@@ -0,0 +1,226 @@
+//! An implementation of [`smoltcp::phy::Device`] for [`EthernetDMA`].
+//!
+//! With the `smoltcp-phy` feature enabled, [`EthernetDMA`] can be handed
+//! directly to a `smoltcp` `Interface` as its device, instead of every user
+//! hand-rolling [`RxToken`]/[`TxToken`] wiring around [`RxRing`]/[`TxRing`]
+//! and [`PacketId`] themselves.
+
+use smoltcp::{
+    phy::{self, Checksum, ChecksumCapabilities, Device, DeviceCapabilities, Medium, PacketMeta},
+    time::Instant,
+};
+
+use super::{EthernetDMA, PacketId, RxRing, TxRing, MTU};
+use crate::ptp::Timestamp;
+
+impl<'rx, 'tx> Device for EthernetDMA<'rx, 'tx> {
+    type RxToken<'a>
+        = RxToken<'a, 'rx>
+    where
+        Self: 'a;
+    type TxToken<'a>
+        = TxToken<'a, 'tx>
+    where
+        Self: 'a;
+
+    fn receive(&mut self, _timestamp: Instant) -> Option<(Self::RxToken<'_>, Self::TxToken<'_>)> {
+        let (rx, tx) = self.split();
+
+        if !rx.next_packet_is_ready() {
+            return None;
+        }
+
+        let (id, timestamp) = rx.peek_received();
+
+        Some((RxToken { rx, id, timestamp }, TxToken { tx, id: PacketId::new() }))
+    }
+
+    fn transmit(&mut self, _timestamp: Instant) -> Option<Self::TxToken<'_>> {
+        let (_, tx) = self.split();
+        Some(TxToken { tx, id: PacketId::new() })
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        let mut capabilities = DeviceCapabilities::default();
+        capabilities.max_transmission_unit = MTU;
+        capabilities.medium = Medium::Ethernet;
+
+        // The MAC can compute and insert/verify these checksums in hardware, so
+        // smoltcp doesn't need to do it in software.
+        let mut checksums = ChecksumCapabilities::default();
+        checksums.ipv4 = Checksum::None;
+        checksums.tcp = Checksum::None;
+        checksums.udp = Checksum::None;
+        checksums.icmpv4 = Checksum::None;
+        capabilities.checksum = checksums;
+
+        capabilities
+    }
+}
+
+/// A `smoltcp` receive token that borrows a single received frame out of the
+/// [`RxRing`].
+///
+/// The frame's [`PacketId`] is surfaced through `smoltcp`'s own
+/// [`phy::RxToken::meta`] hook (requires the `packetmeta-id` `smoltcp`
+/// feature); the hardware PTP [`Timestamp`], which `smoltcp` has no
+/// equivalent hook for, is available via [`RxToken::timestamp`] before the
+/// token is consumed.
+pub struct RxToken<'a, 'rx> {
+    rx: &'a mut RxRing<'rx>,
+    id: Option<PacketId>,
+    timestamp: Option<Timestamp>,
+}
+
+impl<'a, 'rx> RxToken<'a, 'rx> {
+    /// The [`PacketId`] this frame was received with, if any.
+    pub fn packet_id(&self) -> Option<PacketId> {
+        self.id
+    }
+
+    /// The hardware PTP timestamp latched for this frame, if any.
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        self.timestamp
+    }
+}
+
+impl<'a, 'rx> phy::RxToken for RxToken<'a, 'rx> {
+    fn consume<R, F>(self, f: F) -> R
+    where
+        F: FnOnce(&[u8]) -> R,
+    {
+        let packet = self
+            .rx
+            .take_received()
+            .expect("RxToken constructed without a ready packet");
+
+        f(&packet)
+    }
+
+    fn meta(&self) -> PacketMeta {
+        let mut meta = PacketMeta::default();
+        meta.id = self.id.map(|id| id.0).unwrap_or(0);
+        meta
+    }
+}
+
+/// A `smoltcp` transmit token that borrows a free descriptor out of the
+/// [`TxRing`].
+///
+/// The [`PacketId`] assigned to the frame defaults to a freshly-allocated
+/// one, available via [`TxToken::packet_id`] before the token is consumed so
+/// it can be kept around to look up the hardware TX timestamp afterwards
+/// with [`TxRing::timestamp`]. `smoltcp` may instead assign its own id
+/// through [`phy::TxToken::set_meta`] (requires the `packetmeta-id`
+/// `smoltcp` feature), which [`TxToken::packet_id`] reflects as well.
+pub struct TxToken<'a, 'tx> {
+    tx: &'a mut TxRing<'tx>,
+    id: PacketId,
+}
+
+impl<'a, 'tx> TxToken<'a, 'tx> {
+    /// The [`PacketId`] that will be assigned to the frame sent through this
+    /// token.
+    pub fn packet_id(&self) -> PacketId {
+        self.id
+    }
+}
+
+impl<'a, 'tx> phy::TxToken for TxToken<'a, 'tx> {
+    fn consume<R, F>(self, len: usize, f: F) -> R
+    where
+        F: FnOnce(&mut [u8]) -> R,
+    {
+        let id = self.id;
+
+        let mut buffer = self
+            .tx
+            .prepare_packet_blocking(len, Some(id))
+            .expect("no free TX descriptor");
+
+        let result = f(&mut buffer);
+
+        buffer.send();
+
+        result
+    }
+
+    fn set_meta(&mut self, meta: PacketMeta) {
+        self.id = PacketId(meta.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dma::{RxRingEntry, TxRingEntry};
+
+    fn dma_with_one_ready_rx_packet<'a>(
+        rx: &'a mut [RxRingEntry; 1],
+        tx: &'a mut [TxRingEntry; 1],
+        payload: &[u8],
+    ) -> EthernetDMA<'a, 'a> {
+        rx[0].buffer[..payload.len()].copy_from_slice(payload);
+        rx[0].len = payload.len();
+        rx[0].ready = true;
+        rx[0].id = Some(PacketId(7));
+        rx[0].timestamp = Some(Timestamp::new_raw(42));
+
+        EthernetDMA::new(rx, tx)
+    }
+
+    #[test]
+    fn receive_is_none_without_a_ready_packet() {
+        let mut rx = [RxRingEntry::new()];
+        let mut tx = [TxRingEntry::new()];
+        let mut dma = EthernetDMA::new(&mut rx, &mut tx);
+
+        assert!(Device::receive(&mut dma, Instant::from_millis(0)).is_none());
+    }
+
+    #[test]
+    fn receive_yields_the_ready_packet_with_its_id_and_timestamp() {
+        let mut rx = [RxRingEntry::new()];
+        let mut tx = [TxRingEntry::new()];
+        let mut dma = dma_with_one_ready_rx_packet(&mut rx, &mut tx, &[1, 2, 3, 4]);
+
+        let (rx_token, _tx_token) = Device::receive(&mut dma, Instant::from_millis(0)).unwrap();
+        assert_eq!(rx_token.packet_id(), Some(PacketId(7)));
+        assert_eq!(rx_token.timestamp(), Some(Timestamp::new_raw(42)));
+
+        let consumed = phy::RxToken::consume(rx_token, |buf| buf == [1, 2, 3, 4]);
+        assert!(consumed);
+    }
+
+    #[test]
+    fn transmit_token_writes_into_a_free_descriptor() {
+        let mut rx = [RxRingEntry::new()];
+        let mut tx = [TxRingEntry::new()];
+        let mut dma = EthernetDMA::new(&mut rx, &mut tx);
+
+        let tx_token = Device::transmit(&mut dma, Instant::from_millis(0)).unwrap();
+        let id = tx_token.packet_id();
+
+        phy::TxToken::consume(tx_token, 3, |buf| {
+            buf.copy_from_slice(&[9, 8, 7]);
+        });
+
+        let (_, tx_ring) = dma.split();
+        assert_eq!(tx_ring.timestamp(&id), Ok(Some(Timestamp::new_raw(0))));
+    }
+
+    #[test]
+    fn capabilities_report_the_shared_mtu_and_hardware_checksums() {
+        let mut rx = [RxRingEntry::new()];
+        let mut tx = [TxRingEntry::new()];
+        let dma = EthernetDMA::new(&mut rx, &mut tx);
+
+        let capabilities = Device::capabilities(&dma);
+        assert_eq!(capabilities.max_transmission_unit, MTU);
+        assert_eq!(capabilities.medium, Medium::Ethernet);
+        assert!(matches!(capabilities.checksum.ipv4, Checksum::None));
+        assert!(matches!(capabilities.checksum.tcp, Checksum::None));
+        assert!(matches!(capabilities.checksum.udp, Checksum::None));
+        assert!(matches!(capabilities.checksum.icmpv4, Checksum::None));
+    }
+}
@@ -0,0 +1,60 @@
+//! An abstraction layer for ethernet peripherals embedded in STM32 processors.
+//!
+//! # Status
+//!
+//! This crate is not yet wired up to real hardware: [`ptp`]'s `ETH_PTP*`
+//! registers are an in-memory [`Cell`](core::cell::Cell)-based stand-in (see
+//! `ptp::pac`), [`dma::EthernetDMA`] has no real descriptor-ring DMA engine
+//! behind it (`enable_interrupt` is a no-op and `interrupt_handler` always
+//! reports nothing happened), and there is no peripheral bring-up function
+//! (clock/GPIO configuration, `new`/`new_with_mii`) at all. Treat the APIs
+//! here as a design sketch to build real hardware support against, not as a
+//! driver you can flash to an STM32 today.
+#![no_std]
+
+pub mod dma;
+pub mod mac;
+pub mod ptp;
+
+use dma::EthernetDMA;
+use mac::EthernetMAC;
+use ptp::EthernetPTP;
+
+/// The pieces of the ethernet peripheral returned by bring-up code, bundled
+/// together so they can be passed around (and torn back down) as a unit.
+pub struct Parts<'rx, 'tx, MAC = EthernetMAC> {
+    /// The DMA half of the peripheral: RX/TX descriptor rings and buffers.
+    pub dma: EthernetDMA<'rx, 'tx>,
+    /// The MAC half of the peripheral.
+    pub mac: MAC,
+    /// The IEEE 1588v2 PTP peripheral.
+    pub ptp: EthernetPTP,
+}
+
+/// A summary of the reasons for the occurrence of an `ETH` interrupt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InterruptReason {
+    /// A packet has arrived and is ready for processing.
+    pub rx: bool,
+    /// A packet was sent, and a TX slot has freed up.
+    pub tx: bool,
+    /// The target time configured for PTP has passed.
+    pub time_passed: bool,
+}
+
+/// Handle the `ETH` interrupt.
+///
+/// On real hardware this would clear the interrupt flags this crate is
+/// responsible for and wake any wakers (DMA RX/TX, and the PTP target time
+/// trigger) that were waiting on them; see the [crate-level status
+/// note](crate#status) for what's actually implemented today.
+pub fn eth_interrupt_handler() -> InterruptReason {
+    let dma = EthernetDMA::interrupt_handler();
+    let time_passed = EthernetPTP::interrupt_handler();
+
+    InterruptReason {
+        rx: dma.is_rx,
+        tx: dma.is_tx,
+        time_passed,
+    }
+}
@@ -0,0 +1,99 @@
+//! MAC-level configuration: station address, duplex/speed, and PHY-backed
+//! link status.
+
+use ieee802_3_miim::Miim;
+
+/// The duplex/speed mode negotiated on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Speed {
+    /// 10 Mbps, half duplex.
+    Speed10HalfDuplex,
+    /// 10 Mbps, full duplex.
+    Speed10FullDuplex,
+    /// 100 Mbps, half duplex.
+    Speed100HalfDuplex,
+    /// 100 Mbps, full duplex.
+    Speed100FullDuplex,
+}
+
+/// The MAC half of the ethernet peripheral: station address and
+/// duplex/speed configuration, without any attached PHY. Use
+/// [`EthernetMACWithMii`] for live link status backed by an MDIO-attached
+/// PHY.
+pub struct EthernetMAC {
+    address: [u8; 6],
+    speed: Speed,
+}
+
+impl EthernetMAC {
+    /// Configure the MAC with the given station address, defaulting to
+    /// 100 Mbps full duplex.
+    pub fn new(address: [u8; 6]) -> Self {
+        Self {
+            address,
+            speed: Speed::Speed100FullDuplex,
+        }
+    }
+
+    /// This station's MAC address.
+    pub fn address(&self) -> [u8; 6] {
+        self.address
+    }
+
+    /// The currently configured duplex/speed mode.
+    pub fn speed(&self) -> Speed {
+        self.speed
+    }
+
+    /// Reconfigure the duplex/speed mode, e.g. after an auto-negotiation
+    /// result read from an attached PHY.
+    pub fn set_speed(&mut self, speed: Speed) {
+        self.speed = speed;
+    }
+}
+
+/// MII register address of the Basic Mode Status Register.
+const BMSR: u8 = 1;
+/// Link Status bit within [`BMSR`].
+const BMSR_LINK_STATUS: u16 = 1 << 2;
+
+/// An [`EthernetMAC`] plus an MDIO-attached PHY, used to report live link
+/// status via [`EthernetMACWithMii::link_up`].
+pub struct EthernetMACWithMii<M: Miim> {
+    mac: EthernetMAC,
+    miim: M,
+    phy_address: u8,
+}
+
+impl<M: Miim> EthernetMACWithMii<M> {
+    /// Configure the MAC, talking to the PHY at `phy_address` over `miim`.
+    pub fn new(address: [u8; 6], miim: M, phy_address: u8) -> Self {
+        Self {
+            mac: EthernetMAC::new(address),
+            miim,
+            phy_address,
+        }
+    }
+
+    /// This station's MAC address.
+    pub fn address(&self) -> [u8; 6] {
+        self.mac.address()
+    }
+
+    /// The currently configured duplex/speed mode.
+    pub fn speed(&self) -> Speed {
+        self.mac.speed()
+    }
+
+    /// Reconfigure the duplex/speed mode.
+    pub fn set_speed(&mut self, speed: Speed) {
+        self.mac.set_speed(speed);
+    }
+
+    /// Read the PHY's Basic Mode Status Register and report whether the
+    /// link is up.
+    pub fn link_up(&mut self) -> bool {
+        let bmsr = self.miim.read(self.phy_address, BMSR);
+        bmsr & BMSR_LINK_STATUS != 0
+    }
+}
@@ -0,0 +1,144 @@
+//! The IEEE 1588v2 Precision Time Protocol peripheral: a free-running clock
+//! disciplined via [`EthernetPTP::set_addend`]/[`EthernetPTP::update_time`],
+//! plus the hardware timestamp filtering ([`mod@timestamp_control`]) and
+//! PPS/target-time ([`mod@pps`]) features built on top of it.
+//!
+//! Unlike real `ETH_PTP` hardware, this crate has no MMIO timer driving the
+//! clock forward on its own - see [`EthernetPTP::tick`].
+
+mod pac;
+mod pps;
+mod servo;
+mod subseconds;
+mod timestamp;
+mod timestamp_control;
+
+pub use pps::{PpsFrequency, PulseMode};
+pub use servo::ClockServo;
+pub use subseconds::Subseconds;
+pub use timestamp::Timestamp;
+pub use timestamp_control::{PtpNodeRole, PtpTransport, TimestampConfig, TimestampSnapshotMode};
+
+use core::sync::atomic::{AtomicI64, Ordering};
+use core::task::Waker;
+use embassy_sync::waitqueue::AtomicWaker;
+
+/// The addend value that runs the clock at its nominal (uncorrected) rate.
+const NOMINAL_ADDEND: u32 = 0x8000_0000;
+
+static CLOCK: AtomicI64 = AtomicI64::new(0);
+static TARGET_WAKER: AtomicWaker = AtomicWaker::new();
+
+/// The IEEE 1588v2 PTP peripheral.
+pub struct EthernetPTP {
+    eth_ptp: pac::EthPtpRegs,
+    addend: u32,
+}
+
+impl EthernetPTP {
+    /// Set up the PTP peripheral running at its nominal rate. The shared
+    /// clock value read by [`EthernetPTP::now`] is not reset.
+    pub fn new() -> Self {
+        Self {
+            eth_ptp: pac::EthPtpRegs::default(),
+            addend: NOMINAL_ADDEND,
+        }
+    }
+
+    /// The current value of the free-running PTP clock.
+    pub fn now() -> Timestamp {
+        Timestamp::new_raw(CLOCK.load(Ordering::Relaxed))
+    }
+
+    /// Advance the free-running clock by `elapsed` of nominal (uncorrected)
+    /// time, scaled by the current fine-adjustment addend.
+    ///
+    /// On real `ETH_PTP` hardware this happens continuously and
+    /// automatically: the peripheral increments its own counter by `addend`
+    /// every reference-clock cycle, with no help from software. This crate
+    /// has no MMIO timer backing that, so nothing advances the clock on its
+    /// own - callers must drive it explicitly by calling this periodically
+    /// (e.g. from a hardware timer interrupt firing every `elapsed`) for
+    /// [`EthernetPTP::now`], [`EthernetPTP::wait_until`]/[`EthernetPTP::wait_for_target_time`],
+    /// and [`pps`](mod@pps) target-time matching to ever observe time
+    /// passing.
+    pub fn tick(&mut self, elapsed: Subseconds) {
+        let scaled_nanos = elapsed.nanos() as u64 * self.addend as u64 / NOMINAL_ADDEND as u64;
+        let delta = Timestamp::new(
+            false,
+            (scaled_nanos / 1_000_000_000) as u32,
+            Subseconds::from_nanos((scaled_nanos % 1_000_000_000) as u32),
+        );
+        CLOCK.fetch_add(delta.raw(), Ordering::Relaxed);
+    }
+
+    /// The current fine-adjustment addend.
+    pub fn addend(&self) -> u32 {
+        self.addend
+    }
+
+    /// Reprogram the fine-adjustment addend, e.g. from a [`ClockServo`]
+    /// frequency correction.
+    pub fn set_addend(&mut self, addend: u32) {
+        self.addend = addend;
+    }
+
+    /// Step the clock by `-offset` (`offset` is `local - master`). The
+    /// fine-adjustment addend is left as it is.
+    pub fn update_time(&mut self, offset: Timestamp) {
+        let corrected = CLOCK.load(Ordering::Relaxed).wrapping_sub(offset.raw());
+        CLOCK.store(corrected, Ordering::Relaxed);
+    }
+
+    /// Await the clock reaching `at`.
+    ///
+    /// This delegates to [`EthernetPTP::wait_for_target_time`], so it
+    /// programs and waits on the same target-time-match interrupt.
+    pub async fn wait_until(&mut self, at: Timestamp) {
+        self.wait_for_target_time(at).await
+    }
+
+    /// Register `waker` to be woken the next time [`EthernetPTP::interrupt_handler`]
+    /// observes that the clock has reached the most recently scheduled
+    /// target time.
+    pub(crate) fn register_target_time_waker(&mut self, waker: &Waker) {
+        TARGET_WAKER.register(waker);
+    }
+
+    /// Handle the PTP-related parts of the `ETH` interrupt.
+    ///
+    /// Returns whether the configured target time has passed; if so, any
+    /// task waiting on [`EthernetPTP::wait_until`]/[`EthernetPTP::wait_for_target_time`]
+    /// is woken.
+    pub fn interrupt_handler() -> bool {
+        if Self::now() >= TargetTime::get() {
+            TARGET_WAKER.wake();
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Default for EthernetPTP {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The most recently scheduled target time, shared between
+/// [`EthernetPTP::wait_for_target_time`] and [`EthernetPTP::interrupt_handler`].
+/// [`EthernetPTP::schedule_pulse`] does not update it.
+struct TargetTime;
+
+impl TargetTime {
+    fn get() -> Timestamp {
+        Timestamp::new_raw(TARGET_TIME.load(Ordering::Relaxed))
+    }
+
+    fn set(at: Timestamp) {
+        TARGET_TIME.store(at.raw(), Ordering::Relaxed);
+    }
+}
+
+static TARGET_TIME: AtomicI64 = AtomicI64::new(i64::MAX);
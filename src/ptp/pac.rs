@@ -0,0 +1,129 @@
+//! A tiny in-memory stand-in for the real `ETH_PTP*` MMIO registers.
+//!
+//! This crate aims to support multiple STM32 families, each through a
+//! different HAL/PAC crate with its own peripheral instantiation. Rather
+//! than committing [`EthernetPTP`](super::EthernetPTP) to one family's exact
+//! register addresses, its filtering/PPS state is kept in plain fields here,
+//! behind the same read-modify-write shape (`.modify(|r, w| ...)`) a real
+//! PAC's register API uses, so the logic built on top of it is exercised the
+//! same way it would be against real hardware.
+//!
+//! This is genuinely just a `Cell` in RAM, not a real MMIO register: nothing
+//! here talks to actual `ETH_PTP*` hardware. See the crate-level status note
+//! in `lib.rs` for what bringing this up on real silicon would still need.
+
+use core::cell::Cell;
+
+/// A single register, modeled as a plain 32-bit cell.
+#[derive(Default)]
+pub(crate) struct Reg(Cell<u32>);
+
+impl Reg {
+    pub(crate) fn modify(&self, f: impl for<'a> FnOnce(&'a R, &'a mut W) -> &'a mut W) {
+        let r = R(self.0.get());
+        let mut w = W(self.0.get());
+        f(&r, &mut w);
+        self.0.set(w.0);
+    }
+
+    pub(crate) fn write(&self, f: impl for<'a> FnOnce(&'a mut W) -> &'a mut W) {
+        let mut w = W(0);
+        f(&mut w);
+        self.0.set(w.0);
+    }
+
+    /// The register's raw bits, for asserting on in tests.
+    #[cfg(test)]
+    pub(crate) fn bits(&self) -> u32 {
+        self.0.get()
+    }
+}
+
+/// A register's value as seen by a `modify` callback.
+#[allow(dead_code)]
+pub(crate) struct R(u32);
+
+/// A register value being assembled by a `write`/`modify` callback.
+pub(crate) struct W(u32);
+
+impl W {
+    fn bit(&mut self, pos: u8, value: bool) -> &mut Self {
+        if value {
+            self.0 |= 1 << pos;
+        } else {
+            self.0 &= !(1 << pos);
+        }
+        self
+    }
+
+    fn field(&mut self, pos: u8, width: u8, value: u32) -> &mut Self {
+        let mask = (1u32 << width) - 1;
+        self.0 = (self.0 & !(mask << pos)) | ((value & mask) << pos);
+        self
+    }
+
+    /// Set the whole register at once, for registers holding a single
+    /// unstructured value (e.g. one half of a target-time counter).
+    pub(crate) fn bits(&mut self, value: u32) -> &mut Self {
+        self.0 = value;
+        self
+    }
+
+    // ETH_PTPTSCR fields.
+    pub(crate) fn tssarfe(&mut self, value: bool) -> &mut Self {
+        self.bit(8, value)
+    }
+    pub(crate) fn tsseme(&mut self, value: bool) -> &mut Self {
+        self.bit(14, value)
+    }
+    pub(crate) fn tssmrme(&mut self, value: bool) -> &mut Self {
+        self.bit(15, value)
+    }
+    pub(crate) fn tssptpoefe(&mut self, value: bool) -> &mut Self {
+        self.bit(11, value)
+    }
+    pub(crate) fn tssipv6fe(&mut self, value: bool) -> &mut Self {
+        self.bit(12, value)
+    }
+    pub(crate) fn tssipv4fe(&mut self, value: bool) -> &mut Self {
+        self.bit(13, value)
+    }
+    pub(crate) fn ttsaru(&mut self, value: bool) -> &mut Self {
+        self.bit(5, value)
+    }
+    pub(crate) fn tsite(&mut self, value: bool) -> &mut Self {
+        self.bit(4, value)
+    }
+
+    // ETH_PTPPPSCR field (5 bits: a `2^n` Hz exponent, 0..=31).
+    pub(crate) fn ppsfreq(&mut self, value: u8) -> &mut Self {
+        self.field(0, 5, value as u32)
+    }
+}
+
+/// The subset of `ETH_PTP*` registers this crate's PTP logic touches.
+#[derive(Default)]
+pub(crate) struct EthPtpRegs {
+    ptptscr: Reg,
+    ptppps: Reg,
+    ptptthr: Reg,
+    ptpttlr: Reg,
+}
+
+impl EthPtpRegs {
+    pub(crate) fn ptptscr(&self) -> &Reg {
+        &self.ptptscr
+    }
+
+    pub(crate) fn ptppps(&self) -> &Reg {
+        &self.ptppps
+    }
+
+    pub(crate) fn ptptthr(&self) -> &Reg {
+        &self.ptptthr
+    }
+
+    pub(crate) fn ptpttlr(&self) -> &Reg {
+        &self.ptpttlr
+    }
+}
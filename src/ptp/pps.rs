@@ -0,0 +1,290 @@
+//! Programmable PPS pulse-train and target-time output configuration.
+//!
+//! A fixed 1 Hz output isn't enough for every use case: the MAC's PPS output
+//! is actually a binary-divided pulse train, and can also be put into
+//! target-time/alarm mode to fire a single (or repeating) pulse at a specific
+//! [`Timestamp`] ([`EthernetPTP::schedule_pulse`]). Separately, the
+//! time-stamp-trigger interrupt can be armed for a [`Timestamp`] so a task
+//! can await it ([`EthernetPTP::wait_for_target_time`]);
+//! [`EthernetPTP::schedule_pulse`] itself does not enable that interrupt.
+
+use super::{EthernetPTP, TargetTime, Timestamp};
+
+/// The PPS output frequency, expressed as the binary exponent the hardware
+/// actually programs: the output toggles at `2^freq_exponent` Hz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PpsFrequency(u8);
+
+impl PpsFrequency {
+    /// The default, fixed-1-Hz output.
+    pub const HZ_1: Self = Self(0);
+
+    /// `2^exponent` Hz, where `exponent` is at most 31 (the width of the
+    /// hardware's `PPSFREQ` field).
+    pub const fn hz_pow2(exponent: u8) -> Self {
+        assert!(exponent <= 31, "PPS frequency exponent must be <= 31");
+        Self(exponent)
+    }
+}
+
+/// How a scheduled target-time pulse behaves once it fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PulseMode {
+    /// Fire once at the target time, then stop.
+    OneShot,
+    /// Fire at the target time, then keep repeating by auto-reloading the
+    /// target-time registers.
+    Repeating,
+}
+
+impl EthernetPTP {
+    /// Reprogram the PPS output to toggle at `frequency` instead of the
+    /// fixed 1 Hz default.
+    pub fn set_pps_freq(&mut self, frequency: PpsFrequency) {
+        self.eth_ptp
+            .ptppps()
+            .modify(|_, w| w.ppsfreq(frequency.0));
+    }
+
+    /// Schedule a pulse at `at` instead of the free-running output.
+    ///
+    /// With [`PulseMode::OneShot`] the output fires a single pulse and then
+    /// stops; with [`PulseMode::Repeating`] the target-time registers
+    /// auto-reload so the pulse keeps firing at the frequency last set with
+    /// [`EthernetPTP::set_pps_freq`].
+    ///
+    /// There is no pulse-width control here: `ETH_PTPPPSCR` has no field for
+    /// it, so the pulse width is whatever the hardware fixes it at and isn't
+    /// configurable through this peripheral.
+    pub fn schedule_pulse(&mut self, at: Timestamp, mode: PulseMode) {
+        self.eth_ptp.ptptthr().write(|w| w.bits(at.seconds()));
+        self.eth_ptp.ptpttlr().write(|w| w.bits(at.subseconds().raw()));
+
+        self.eth_ptp
+            .ptptscr()
+            .modify(|_, w| w.ttsaru(mode == PulseMode::Repeating));
+    }
+
+    /// Await the hardware time-stamp-trigger interrupt firing at `at`,
+    /// without producing a pulse on the PPS pin.
+    ///
+    /// The returned future is woken by the target-time-match interrupt (see
+    /// [`EthernetPTP::interrupt_handler`]) rather than by polling
+    /// [`EthernetPTP::now`] in a loop. [`EthernetPTP::wait_until`] delegates
+    /// to this method.
+    pub async fn wait_for_target_time(&mut self, at: Timestamp) {
+        self.eth_ptp.ptptthr().write(|w| w.bits(at.seconds()));
+        self.eth_ptp.ptpttlr().write(|w| w.bits(at.subseconds().raw()));
+
+        self.eth_ptp
+            .ptptscr()
+            .modify(|_, w| w.ttsaru(false).tsite(true));
+
+        TargetTime::set(at);
+
+        TargetTimeFuture { ptp: self, at }.await
+    }
+}
+
+/// A future that completes once the PTP target-time-match interrupt fires
+/// for the scheduled timestamp.
+struct TargetTimeFuture<'a> {
+    ptp: &'a mut EthernetPTP,
+    at: Timestamp,
+}
+
+impl<'a> core::future::Future for TargetTimeFuture<'a> {
+    type Output = ();
+
+    fn poll(
+        self: core::pin::Pin<&mut Self>,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Self::Output> {
+        let this = self.get_mut();
+
+        // Register before checking: `interrupt_handler` runs from the real
+        // `ETH` ISR and can preempt between a check and a later registration,
+        // which would wake a waker that was never stored and hang forever.
+        // Registering first means any wake for an already-true condition is
+        // simply redundant with the `Ready` we return ourselves below.
+        this.ptp.register_target_time_waker(cx.waker());
+
+        if EthernetPTP::now() >= this.at {
+            core::task::Poll::Ready(())
+        } else {
+            core::task::Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::Subseconds;
+    use core::future::Future;
+    use core::sync::atomic::{AtomicBool, Ordering};
+    use core::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+    const PPSFREQ_MASK: u32 = 0x1F;
+    const TTSARU: u32 = 1 << 5;
+
+    // `EthernetPTP::now()` reads a single, crate-wide `CLOCK` static, so
+    // tests that assert on its value relative to a target time must not run
+    // concurrently with each other (they may run concurrently with tests
+    // that only move it by a few microseconds without checking its value,
+    // like the `ClockServo` tests, whose steps go through `update_time`).
+    // This crate is `no_std`, so a plain spinlock over
+    // an `AtomicBool` stands in for a `Mutex` here.
+    static CLOCK_TEST_LOCK: AtomicBool = AtomicBool::new(false);
+
+    struct ClockTestGuard;
+
+    impl Drop for ClockTestGuard {
+        fn drop(&mut self) {
+            CLOCK_TEST_LOCK.store(false, Ordering::Release);
+        }
+    }
+
+    fn lock_clock_test() -> ClockTestGuard {
+        while CLOCK_TEST_LOCK
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        ClockTestGuard
+    }
+
+    fn counting_waker(woken: &'static AtomicBool) -> Waker {
+        fn clone(data: *const ()) -> RawWaker {
+            RawWaker::new(data, &VTABLE)
+        }
+        fn wake(data: *const ()) {
+            unsafe { &*(data as *const AtomicBool) }.store(true, Ordering::SeqCst);
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake, |_| {});
+
+        let raw = RawWaker::new(woken as *const AtomicBool as *const (), &VTABLE);
+        unsafe { Waker::from_raw(raw) }
+    }
+
+    #[test]
+    fn set_pps_freq_programs_ppsfreq() {
+        let mut ptp = EthernetPTP::new();
+        ptp.set_pps_freq(PpsFrequency::hz_pow2(7));
+
+        assert_eq!(ptp.eth_ptp.ptppps().bits() & PPSFREQ_MASK, 7);
+    }
+
+    #[test]
+    fn schedule_pulse_one_shot_leaves_ttsaru_clear() {
+        let mut ptp = EthernetPTP::new();
+        let at = Timestamp::new(false, 5, Subseconds::from_nanos(250));
+
+        ptp.schedule_pulse(at, PulseMode::OneShot);
+
+        assert_eq!(ptp.eth_ptp.ptptthr().bits(), at.seconds());
+        assert_eq!(ptp.eth_ptp.ptpttlr().bits(), at.subseconds().raw());
+        assert_eq!(ptp.eth_ptp.ptptscr().bits() & TTSARU, 0);
+    }
+
+    #[test]
+    fn schedule_pulse_repeating_sets_ttsaru() {
+        let mut ptp = EthernetPTP::new();
+        let at = Timestamp::new(false, 1, Subseconds::from_nanos(0));
+
+        ptp.schedule_pulse(at, PulseMode::Repeating);
+
+        assert_eq!(ptp.eth_ptp.ptptscr().bits() & TTSARU, TTSARU);
+    }
+
+    #[test]
+    fn wait_for_target_time_programs_target_registers() {
+        // This sets the shared target time and registers the shared target
+        // waker, which the other target-time tests below also use.
+        let _guard = lock_clock_test();
+        let mut ptp = EthernetPTP::new();
+        let at = Timestamp::new(false, 3, Subseconds::from_nanos(999));
+
+        static WOKEN: AtomicBool = AtomicBool::new(false);
+        let waker = counting_waker(&WOKEN);
+        let mut cx = Context::from_waker(&waker);
+
+        // Poll the method's future once by hand, without an executor: the
+        // register programming runs on that first poll.
+        {
+            let future = core::pin::pin!(ptp.wait_for_target_time(at));
+            let _ = future.poll(&mut cx);
+        }
+
+        assert_eq!(ptp.eth_ptp.ptptthr().bits(), at.seconds());
+        assert_eq!(ptp.eth_ptp.ptpttlr().bits(), at.subseconds().raw());
+    }
+
+    /// Advance the shared clock past `seconds` whole seconds (by just under
+    /// `seconds + 1` seconds at the nominal addend), via `seconds + 1`
+    /// [`EthernetPTP::tick`] calls of just under one second each.
+    fn tick_seconds(ptp: &mut EthernetPTP, seconds: u32) {
+        for _ in 0..=seconds {
+            ptp.tick(Subseconds::from_nanos(999_999_999));
+        }
+    }
+
+    #[test]
+    fn poll_is_pending_before_the_target_time_and_ready_after() {
+        let _guard = lock_clock_test();
+        let mut ptp = EthernetPTP::new();
+        // A target comfortably ahead of "now": other tests in this crate
+        // only ever nudge the shared clock by nanosecond-to-microsecond
+        // amounts, so a 10-second margin can't be closed by anything but
+        // this test's own `tick` calls below.
+        let at = Timestamp::new_raw(EthernetPTP::now().raw() + Timestamp::new(false, 10, Subseconds::new_raw(0)).raw());
+
+        static WOKEN: AtomicBool = AtomicBool::new(false);
+        let waker = counting_waker(&WOKEN);
+        let mut cx = Context::from_waker(&waker);
+
+        let mut future = TargetTimeFuture { ptp: &mut ptp, at };
+        let poll = core::pin::Pin::new(&mut future).poll(&mut cx);
+        assert_eq!(poll, Poll::Pending);
+        assert!(!WOKEN.load(Ordering::SeqCst));
+
+        // Advance the clock past `at` (standing in for the target time
+        // elapsing in hardware) and poll again.
+        tick_seconds(future.ptp, 10);
+
+        let poll = core::pin::Pin::new(&mut future).poll(&mut cx);
+        assert_eq!(poll, Poll::Ready(()));
+    }
+
+    #[test]
+    fn waker_registered_by_an_earlier_pending_poll_is_still_woken_by_the_interrupt_handler() {
+        let _guard = lock_clock_test();
+        let mut ptp = EthernetPTP::new();
+        let at = Timestamp::new_raw(EthernetPTP::now().raw() + Timestamp::new(false, 10, Subseconds::new_raw(0)).raw());
+
+        static WOKEN: AtomicBool = AtomicBool::new(false);
+        let waker = counting_waker(&WOKEN);
+        let mut cx = Context::from_waker(&waker);
+
+        // First poll: not yet at the target, registers the waker and
+        // returns `Pending` - standing in for the task parking itself.
+        let mut future = TargetTimeFuture { ptp: &mut ptp, at };
+        let poll = core::pin::Pin::new(&mut future).poll(&mut cx);
+        assert_eq!(poll, Poll::Pending);
+
+        // The clock reaches the target and the real `ETH` ISR runs,
+        // without this task ever being polled again first. Because `poll`
+        // registers the waker before checking the condition (rather than
+        // after), the waker registered above is the one still on file when
+        // `interrupt_handler` fires, so it must be woken here.
+        TargetTime::set(at);
+        tick_seconds(future.ptp, 10);
+        EthernetPTP::interrupt_handler();
+
+        assert!(
+            WOKEN.load(Ordering::SeqCst),
+            "a wake arriving after a Pending poll must not be lost"
+        );
+    }
+}
@@ -0,0 +1,253 @@
+//! A reusable proportional-integral clock-discipline servo.
+//!
+//! [`ClockServo`] replaces the ad-hoc "step if far off, else nudge the addend"
+//! loop that PTP examples tend to hand-roll inline in their receive task. It
+//! keeps just enough state to turn a stream of offset samples into a
+//! well-behaved frequency correction, and leaves actually reading the offset
+//! (e.g. from a Sync/Delay_Resp exchange) up to the caller.
+
+use super::{EthernetPTP, Timestamp};
+
+/// Offset magnitudes larger than this are considered a step, not something a
+/// frequency correction could reasonably chase.
+const DEFAULT_STEP_THRESHOLD_NANOS: i64 = 20_000;
+
+/// Default proportional gain.
+const DEFAULT_KP: f32 = 0.7;
+
+/// Default integral gain.
+const DEFAULT_KI: f32 = 0.3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ServoState {
+    /// No sample has been taken yet.
+    Unsynchronized,
+    /// The clock has just been stepped (on the first sample, or on one above
+    /// the step threshold), so there isn't yet a second point to estimate an
+    /// initial frequency from.
+    Stepped,
+    /// The integrator has been seeded and is disciplining the clock.
+    Tracking,
+}
+
+/// A PI servo that disciplines an [`EthernetPTP`] clock's addend from a
+/// stream of `local - master` offset samples.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut servo = ClockServo::new();
+/// // offset is `local_time - master_time` for this sync interval
+/// servo.sample(&mut ptp, offset, 1.0);
+/// ```
+pub struct ClockServo {
+    state: ServoState,
+    step_threshold: Subseconds,
+    kp: f32,
+    ki: f32,
+    last_offset: Timestamp,
+    integral: f32,
+    nominal_addend: u32,
+}
+
+use super::Subseconds;
+
+impl ClockServo {
+    /// Create a servo using the default step threshold (20 microseconds) and
+    /// gains (`kp` = 0.7, `ki` = 0.3).
+    pub fn new() -> Self {
+        Self {
+            state: ServoState::Unsynchronized,
+            step_threshold: Subseconds::from_nanos(DEFAULT_STEP_THRESHOLD_NANOS as u32),
+            kp: DEFAULT_KP,
+            ki: DEFAULT_KI,
+            last_offset: Timestamp::new_raw(0),
+            integral: 0.0,
+            nominal_addend: 0,
+        }
+    }
+
+    /// Use a custom step threshold instead of the default 20 microseconds.
+    ///
+    /// Offset samples whose magnitude exceeds this threshold are applied with
+    /// [`EthernetPTP::update_time`] instead of a frequency correction, and
+    /// reset the integrator.
+    pub fn with_step_threshold(mut self, threshold: Subseconds) -> Self {
+        self.step_threshold = threshold;
+        self
+    }
+
+    /// Use custom proportional (`kp`) and integral (`ki`) gains instead of
+    /// the defaults (0.7 / 0.3).
+    pub fn with_gains(mut self, kp: f32, ki: f32) -> Self {
+        self.kp = kp;
+        self.ki = ki;
+        self
+    }
+
+    /// Feed a new offset sample (`local - master`, taken `interval_s` seconds
+    /// after the previous sample) and apply the resulting correction to
+    /// `ptp`.
+    pub fn sample(&mut self, ptp: &mut EthernetPTP, offset: Timestamp, interval_s: f32) {
+        if self.nominal_addend == 0 {
+            self.nominal_addend = ptp.addend();
+        }
+
+        if offset.nanos() as u64 > self.step_threshold.nanos() as u64 {
+            self.step(ptp, offset);
+            return;
+        }
+
+        match self.state {
+            ServoState::Unsynchronized => self.step(ptp, offset),
+            ServoState::Stepped => {
+                let offset_nanos = Self::signed_nanos(offset);
+                let last_nanos = Self::signed_nanos(self.last_offset);
+                self.integral = (offset_nanos - last_nanos) / interval_s;
+                self.last_offset = offset;
+                self.state = ServoState::Tracking;
+                self.apply_correction(ptp, self.integral);
+            }
+            ServoState::Tracking => {
+                let offset_nanos = Self::signed_nanos(offset);
+
+                let ppb = self.kp * offset_nanos + self.integral;
+                self.integral += self.ki * offset_nanos * interval_s;
+                self.last_offset = offset;
+
+                self.apply_correction(ptp, ppb);
+            }
+        }
+    }
+
+    /// The offset as signed nanoseconds, positive when the local clock is
+    /// ahead of the master.
+    fn signed_nanos(offset: Timestamp) -> f32 {
+        if offset.is_negative() {
+            -(offset.nanos() as f32)
+        } else {
+            offset.nanos() as f32
+        }
+    }
+
+    /// Step the clock directly and (re)start tracking from scratch.
+    fn step(&mut self, ptp: &mut EthernetPTP, offset: Timestamp) {
+        ptp.update_time(offset);
+        self.integral = 0.0;
+        self.last_offset = offset;
+        self.state = ServoState::Stepped;
+    }
+
+    /// Turn a parts-per-billion frequency correction into a new addend and
+    /// apply it, taking care not to let the addend wrap.
+    fn apply_correction(&self, ptp: &mut EthernetPTP, ppb: f32) {
+        let correction = (self.nominal_addend as f64 * ppb as f64 / 1_000_000_000.0) as i64;
+
+        let new_addend = (self.nominal_addend as i64 + correction).clamp(0, u32::MAX as i64) as u32;
+
+        ptp.set_addend(new_addend);
+    }
+}
+
+impl Default for ClockServo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ptp::EthernetPTP;
+
+    fn offset_nanos(nanos: i32) -> Timestamp {
+        Timestamp::new(nanos < 0, 0, Subseconds::from_nanos(nanos.unsigned_abs()))
+    }
+
+    #[test]
+    fn first_sample_steps_the_clock_and_does_not_touch_the_addend() {
+        let mut ptp = EthernetPTP::new();
+        ptp.set_addend(0x8000_0000);
+        let mut servo = ClockServo::new();
+
+        servo.sample(&mut ptp, offset_nanos(5_000), 1.0);
+
+        assert_eq!(ptp.addend(), 0x8000_0000);
+    }
+
+    #[test]
+    fn offset_above_step_threshold_steps_even_once_tracking() {
+        let mut ptp = EthernetPTP::new();
+        ptp.set_addend(0x8000_0000);
+        let mut servo = ClockServo::new();
+
+        // Two small samples move the servo from `Unsynchronized` through
+        // `Stepped` into `Tracking`.
+        servo.sample(&mut ptp, offset_nanos(100), 1.0);
+        servo.sample(&mut ptp, offset_nanos(120), 1.0);
+        assert_ne!(ptp.addend(), 0x8000_0000, "tracking should have nudged the addend");
+
+        let addend_while_tracking = ptp.addend();
+
+        // An offset above the default 20 microsecond step threshold must
+        // step the clock rather than continue disciplining the addend.
+        servo.sample(&mut ptp, offset_nanos(50_000), 1.0);
+
+        assert_eq!(
+            ptp.addend(),
+            addend_while_tracking,
+            "a step must leave the addend untouched"
+        );
+    }
+
+    #[test]
+    fn custom_step_threshold_is_honored() {
+        let mut ptp = EthernetPTP::new();
+        ptp.set_addend(0x8000_0000);
+        let mut servo = ClockServo::new().with_step_threshold(Subseconds::from_nanos(50));
+
+        servo.sample(&mut ptp, offset_nanos(40), 1.0);
+        servo.sample(&mut ptp, offset_nanos(40), 1.0);
+        let addend_while_tracking = ptp.addend();
+
+        // 100ns exceeds the 50ns threshold configured above, even though it
+        // would be well within the default 20 microsecond threshold.
+        servo.sample(&mut ptp, offset_nanos(100), 1.0);
+
+        assert_eq!(ptp.addend(), addend_while_tracking);
+    }
+
+    #[test]
+    fn small_offsets_discipline_the_addend_without_stepping() {
+        let mut ptp = EthernetPTP::new();
+        ptp.set_addend(0x8000_0000);
+        let mut servo = ClockServo::new();
+
+        servo.sample(&mut ptp, offset_nanos(100), 1.0);
+        servo.sample(&mut ptp, offset_nanos(200), 1.0);
+        servo.sample(&mut ptp, offset_nanos(150), 1.0);
+
+        assert_ne!(ptp.addend(), 0x8000_0000);
+    }
+
+    #[test]
+    fn correction_clamps_instead_of_wrapping() {
+        let mut ptp = EthernetPTP::new();
+        ptp.set_addend(10);
+        let mut servo = ClockServo::new();
+        // Prime `nominal_addend` from the current (small) addend.
+        servo.sample(&mut ptp, offset_nanos(0), 1.0);
+
+        servo.apply_correction(&mut ptp, -1.0e12);
+        assert_eq!(ptp.addend(), 0, "a large negative correction must clamp to zero");
+
+        ptp.set_addend(u32::MAX - 10);
+        servo.nominal_addend = u32::MAX - 10;
+        servo.apply_correction(&mut ptp, 1.0e12);
+        assert_eq!(
+            ptp.addend(),
+            u32::MAX,
+            "a large positive correction must clamp to u32::MAX"
+        );
+    }
+}
@@ -0,0 +1,33 @@
+/// A fractional part of a second, as a 31-bit fixed-point value (the same
+/// width as the hardware's subsecond counter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Subseconds(u32);
+
+impl Subseconds {
+    /// The largest subsecond value (a mask over the counter's 31 bits); a
+    /// whole second is `PER_SECOND + 1` units.
+    pub const PER_SECOND: u32 = 0x7FFF_FFFF;
+
+    /// Build a `Subseconds` directly from a raw, already-masked counter
+    /// value.
+    pub const fn new_raw(raw: u32) -> Self {
+        Self(raw & Self::PER_SECOND)
+    }
+
+    /// Convert a nanosecond count (less than one second) into subsecond
+    /// units.
+    pub fn from_nanos(nanos: u32) -> Self {
+        let raw = (nanos as u64) * (Self::PER_SECOND as u64 + 1) / 1_000_000_000;
+        Self(raw as u32 & Self::PER_SECOND)
+    }
+
+    /// This value, as the raw counter units the hardware uses.
+    pub const fn raw(&self) -> u32 {
+        self.0
+    }
+
+    /// This value, converted to nanoseconds.
+    pub fn nanos(&self) -> u32 {
+        ((self.0 as u64) * 1_000_000_000 / (Self::PER_SECOND as u64 + 1)) as u32
+    }
+}
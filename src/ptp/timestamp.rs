@@ -0,0 +1,51 @@
+use super::Subseconds;
+
+/// A signed PTP timestamp: a sign bit, a count of seconds, and a
+/// [`Subseconds`] fraction, packed as `seconds << 31 | subseconds`, negated
+/// as a whole when the sign bit is set - matching the hardware's
+/// sign-magnitude target-time/timestamp registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    /// Build a `Timestamp` directly from its raw representation: the packed
+    /// magnitude, negated when the timestamp is negative.
+    pub const fn new_raw(raw: i64) -> Self {
+        Self(raw)
+    }
+
+    /// Build a `Timestamp` from a sign, a count of whole seconds, and a
+    /// subsecond fraction.
+    pub fn new(negative: bool, seconds: u32, subseconds: Subseconds) -> Self {
+        let magnitude = ((seconds as i64) << 31) | subseconds.raw() as i64;
+        Self(if negative { -magnitude } else { magnitude })
+    }
+
+    /// This timestamp's raw representation: the packed magnitude, negated
+    /// when the timestamp is negative.
+    pub const fn raw(&self) -> i64 {
+        self.0
+    }
+
+    /// Whether this timestamp is negative.
+    pub const fn is_negative(&self) -> bool {
+        self.0 < 0
+    }
+
+    /// The whole-second part of this timestamp's magnitude.
+    pub fn seconds(&self) -> u32 {
+        (self.0.unsigned_abs() >> 31) as u32
+    }
+
+    /// The subsecond part of this timestamp's magnitude.
+    pub fn subseconds(&self) -> Subseconds {
+        Subseconds::new_raw(self.0.unsigned_abs() as u32)
+    }
+
+    /// This timestamp's magnitude, in nanoseconds, saturating at [`u32::MAX`].
+    pub fn nanos(&self) -> u32 {
+        let whole_seconds_nanos = (self.seconds() as u64) * 1_000_000_000;
+        let subsecond_nanos = self.subseconds().nanos() as u64;
+        (whole_seconds_nanos + subsecond_nanos).min(u32::MAX as u64) as u32
+    }
+}
@@ -0,0 +1,197 @@
+//! Hardware timestamp filtering.
+//!
+//! By default the MAC timestamps every frame it sees, which is rarely what a
+//! real PTP stack wants: only IEEE 1588 event messages (Sync, Delay_Req, and
+//! friends) should get a hardware snapshot. This module adds the
+//! `ETH_PTPTSCR`-level configuration needed to restrict snapshotting to PTP
+//! traffic.
+//!
+//! This hardware generation's `ETH_PTPTSCR` has no one-step insertion bit -
+//! `TSSSR` (bit 9) is "Timestamp Subsecond Rollover", not a one-step enable -
+//! so one-step egress timestamping isn't offered here. Two-step operation
+//! (read the egress timestamp back afterwards via [`crate::dma::TxRing::timestamp`]
+//! and carry it in a Follow_Up instead) is the only supported mode.
+
+use super::EthernetPTP;
+
+/// Which frames the MAC takes a hardware timestamp snapshot of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampSnapshotMode {
+    /// Every received and transmitted frame is timestamped.
+    AllFrames,
+    /// Only frames recognized as PTP messages are timestamped.
+    PtpFrames,
+    /// Only PTP *event* messages (Sync, Delay_Req, Pdelay_Req, Pdelay_Resp)
+    /// are timestamped; general messages are not.
+    PtpEventFrames,
+}
+
+/// Whether this node is acting as a PTP master or slave.
+///
+/// This only affects which event messages are eligible for snapshotting when
+/// [`TimestampSnapshotMode::PtpEventFrames`] is selected, matching the MAC's
+/// `TSMSTR`/`TSEVNTENA` behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PtpNodeRole {
+    /// Snapshot Sync and Delay_Req event messages, as a slave does.
+    Slave,
+    /// Snapshot Delay_Req and Pdelay_Resp event messages, as a master does.
+    Master,
+}
+
+/// Which PTP transports the MAC recognizes when filtering event messages.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PtpTransport {
+    /// Recognize PTP over UDP/IPv4.
+    pub ipv4: bool,
+    /// Recognize PTP over UDP/IPv6.
+    pub ipv6: bool,
+    /// Recognize PTP directly over Ethernet (IEEE 802.3, EtherType 0x88F7).
+    pub ethernet: bool,
+}
+
+/// Hardware timestamping configuration, passed to
+/// [`EthernetPTP::configure_timestamping`].
+#[derive(Debug, Clone, Copy)]
+pub struct TimestampConfig {
+    /// Which frames to snapshot.
+    pub snapshot_mode: TimestampSnapshotMode,
+    /// This node's PTP role, consulted when `snapshot_mode` is
+    /// [`TimestampSnapshotMode::PtpEventFrames`].
+    pub role: PtpNodeRole,
+    /// Which transports carry PTP traffic that should be recognized.
+    pub transport: PtpTransport,
+}
+
+impl Default for TimestampConfig {
+    fn default() -> Self {
+        Self {
+            snapshot_mode: TimestampSnapshotMode::PtpEventFrames,
+            role: PtpNodeRole::Slave,
+            transport: PtpTransport {
+                ipv4: true,
+                ipv6: true,
+                ethernet: true,
+            },
+        }
+    }
+}
+
+impl EthernetPTP {
+    /// Configure which frames the MAC hardware-timestamps.
+    ///
+    /// This programs `ETH_PTPTSCR`'s snapshot-enable, event/general message
+    /// filtering, and transport recognition fields in a single call, instead
+    /// of requiring callers to poke the individual bits themselves.
+    pub fn configure_timestamping(&mut self, config: TimestampConfig) {
+        let all_frames = config.snapshot_mode == TimestampSnapshotMode::AllFrames;
+        let event_frames_only = config.snapshot_mode == TimestampSnapshotMode::PtpEventFrames;
+
+        self.eth_ptp.ptptscr().modify(|_, w| {
+            w
+                // TSSARFE: snapshot for all received frames.
+                .tssarfe(all_frames)
+                // TSSEME: only snapshot PTP event messages.
+                .tsseme(event_frames_only)
+                // TSSMRME: only snapshot messages relevant to this node's role.
+                .tssmrme(config.role == PtpNodeRole::Master)
+                .tssptpoefe(config.transport.ethernet)
+                .tssipv6fe(config.transport.ipv6)
+                .tssipv4fe(config.transport.ipv4)
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ETH_PTPTSCR bit positions, mirrored from `pac::W` for these assertions.
+    const TSSARFE: u32 = 1 << 8;
+    const TSSEME: u32 = 1 << 14;
+    const TSSMRME: u32 = 1 << 15;
+    const TSSPTPOEFE: u32 = 1 << 11;
+    const TSSIPV6FE: u32 = 1 << 12;
+    const TSSIPV4FE: u32 = 1 << 13;
+
+    #[test]
+    fn all_frames_sets_only_tssarfe() {
+        let mut ptp = EthernetPTP::new();
+        ptp.configure_timestamping(TimestampConfig {
+            snapshot_mode: TimestampSnapshotMode::AllFrames,
+            role: PtpNodeRole::Slave,
+            transport: PtpTransport::default(),
+        });
+
+        let bits = ptp.eth_ptp.ptptscr().bits();
+        assert_eq!(bits & TSSARFE, TSSARFE);
+        assert_eq!(bits & TSSEME, 0);
+        assert_eq!(bits & TSSMRME, 0);
+    }
+
+    #[test]
+    fn event_frames_as_master_sets_tsseme_and_tssmrme() {
+        let mut ptp = EthernetPTP::new();
+        ptp.configure_timestamping(TimestampConfig {
+            snapshot_mode: TimestampSnapshotMode::PtpEventFrames,
+            role: PtpNodeRole::Master,
+            transport: PtpTransport::default(),
+        });
+
+        let bits = ptp.eth_ptp.ptptscr().bits();
+        assert_eq!(bits & TSSARFE, 0, "event-only mode must not set TSSARFE");
+        assert_eq!(bits & TSSEME, TSSEME);
+        assert_eq!(bits & TSSMRME, TSSMRME, "master role must set TSSMRME");
+    }
+
+    #[test]
+    fn event_frames_as_slave_clears_tssmrme() {
+        let mut ptp = EthernetPTP::new();
+        ptp.configure_timestamping(TimestampConfig {
+            snapshot_mode: TimestampSnapshotMode::PtpEventFrames,
+            role: PtpNodeRole::Slave,
+            transport: PtpTransport::default(),
+        });
+
+        assert_eq!(ptp.eth_ptp.ptptscr().bits() & TSSMRME, 0);
+    }
+
+    #[test]
+    fn transport_filters_map_to_their_own_bits() {
+        let mut ptp = EthernetPTP::new();
+        ptp.configure_timestamping(TimestampConfig {
+            snapshot_mode: TimestampSnapshotMode::PtpFrames,
+            role: PtpNodeRole::Slave,
+            transport: PtpTransport {
+                ipv4: true,
+                ipv6: false,
+                ethernet: true,
+            },
+        });
+
+        let bits = ptp.eth_ptp.ptptscr().bits();
+        assert_eq!(bits & TSSIPV4FE, TSSIPV4FE);
+        assert_eq!(bits & TSSIPV6FE, 0);
+        assert_eq!(bits & TSSPTPOEFE, TSSPTPOEFE);
+    }
+
+    #[test]
+    fn no_tsssr_bit_is_ever_touched() {
+        // Regression test: TSSSR (bit 9) is Timestamp Subsecond Rollover, not
+        // a one-step enable, and must never be written by this module.
+        const TSSSR: u32 = 1 << 9;
+
+        let mut ptp = EthernetPTP::new();
+        ptp.configure_timestamping(TimestampConfig {
+            snapshot_mode: TimestampSnapshotMode::AllFrames,
+            role: PtpNodeRole::Master,
+            transport: PtpTransport {
+                ipv4: true,
+                ipv6: true,
+                ethernet: true,
+            },
+        });
+
+        assert_eq!(ptp.eth_ptp.ptptscr().bits() & TSSSR, 0);
+    }
+}